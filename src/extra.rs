@@ -1,9 +1,14 @@
 
 use std::collections::HashMap;
+use std::fmt::Display;
 
 use serde::Serialize;
-use axum::http::StatusCode;
-use serde_json::{to_value, Value};
+use axum::http::{StatusCode, Uri};
+use axum::response::Redirect;
+use axum::http::status::InvalidStatusCode;
+use serde_json::{to_string, to_value, Value};
+
+use crate::{AxumResult, HttpResponse};
 
 pub trait ToJson where Self: Serialize {
 
@@ -31,7 +36,6 @@ pub trait ToJson where Self: Serialize {
     /// };
     /// 
     /// let json_value: Value = my_struct.to_json();
-
     fn to_json(&self) -> Value {
         to_value(self).unwrap_or(Value::Null)
     }
@@ -41,15 +45,70 @@ impl ToJson for HashMap<String, String> {}
 impl ToJson for HashMap<&'static str, &'static str> {}
 impl<T> ToJson for Vec<T> where T: Serialize {}
 
+/// Serialize every item as a single JSON line and join them with `\n`,
+/// returning the NDJSON text as a JSON string value.
+/// Items that can't be serialized are written as `null`
+///
+/// This is meant to embed NDJSON inside a regular response field,
+/// it does not stream the items.
+///
+/// # Example
+///
+/// ```
+/// use serde_json::{json, Value};
+/// use axum_responses::extra::to_ndjson;
+///
+/// let ndjson: Value = to_ndjson(vec![json!({ "id": 1 }), json!({ "id": 2 })]);
+///
+/// assert_eq!(ndjson, Value::String("{\"id\":1}\n{\"id\":2}".to_string()));
+/// ```
+pub fn to_ndjson<I, T>(items: I) -> Value where I: IntoIterator<Item = T>, T: Serialize {
+
+    let lines = items.into_iter()
+        .map(|item| to_string(&item).unwrap_or("null".to_string()))
+        .collect::<Vec<String>>()
+    ;
+
+    Value::String(lines.join("\n"))
+}
+
+/// Collect the `Display` output of every error into a JSON array of strings,
+/// useful to report several underlying issues under an `errors` key
+///
+/// # Example
+///
+/// ```
+/// use serde_json::json;
+/// use axum_responses::HttpResponse;
+/// use axum_responses::extra::to_error_list;
+///
+/// let errors = vec!["email is required", "password is too short"];
+///
+/// assert_eq!(to_error_list(&errors), json!(["email is required", "password is too short"]));
+///
+/// let response = HttpResponse::JSON(400, "Bad Request", "errors", to_error_list(errors));
+/// ```
+pub fn to_error_list<I, E>(errors: I) -> Value where I: IntoIterator<Item = E>, E: Display {
+    Value::Array(errors.into_iter().map(|error| Value::String(error.to_string())).collect())
+}
+
 /// Convert a u16 status code to a StatusCode
 /// 
-/// If the provided code is not a valid status code,
-/// the function will return a 500 Internal Server Error
-
+/// This conversion is lossy: if the provided code is not a valid 
+/// status code, the function will return a 500 Internal Server Error.
+/// Use `try_http_status` to detect invalid codes instead.
 pub fn to_http_status(code: u16) -> StatusCode {
     StatusCode::from_u16(code).unwrap_or(StatusCode::INTERNAL_SERVER_ERROR)
 }
 
+/// Convert a u16 status code to a StatusCode
+/// 
+/// Unlike `to_http_status`, an invalid code is returned as an error
+/// instead of silently falling back to a 500 Internal Server Error
+pub fn try_http_status(code: u16) -> Result<StatusCode, InvalidStatusCode> {
+    StatusCode::from_u16(code)
+}
+
 pub fn res_type(code: &StatusCode) -> &str {
 
     match code.is_success() {
@@ -57,3 +116,239 @@ pub fn res_type(code: &StatusCode) -> &str {
         false => "error"
     }
 }
+
+/// Build a 303 See Other redirect only if the location is safe,
+/// preventing open redirects when the location comes from user input
+/// 
+/// Relative locations (e.g. `/dashboard?tab=1`) are always allowed,
+/// absolute `http`/`https` locations are allowed only when their host is 
+/// in `allowed_hosts` (case-insensitive). Protocol-relative locations 
+/// (`//evil.com`), userinfo (`https://trusted.com@evil.com`), backslashes,
+/// whitespace, control characters and any other scheme are rejected
+/// with a 400 Bad Request HttpResponse
+/// 
+/// # Example
+/// 
+/// ```
+/// use axum::response::Redirect;
+/// use axum_responses::AxumResult;
+/// use axum_responses::extra::checked_redirect;
+/// 
+/// async fn login_callback() -> AxumResult<Redirect> {
+///     checked_redirect("https://app.example.com/home", &["app.example.com"])
+/// }
+/// 
+/// assert!(checked_redirect("/home", &[]).is_ok());
+/// assert!(checked_redirect("//evil.com", &["app.example.com"]).is_err());
+/// ```
+pub fn checked_redirect(location: &str, allowed_hosts: &[&str]) -> AxumResult<Redirect> {
+
+    match is_safe_location(location, allowed_hosts) {
+        true => Ok(Redirect::to(location)),
+        false => Err(HttpResponse::CUSTOM(400, "Invalid redirect location"))
+    }
+}
+
+/// Build a 303 See Other redirect that sends external targets through an
+/// interstitial page (e.g. a "you are leaving this site" warning), passing
+/// the percent-encoded target in the `target` query parameter
+/// 
+/// Targets accepted by `checked_redirect` for the given `internal_hosts`
+/// (relative locations or allowlisted hosts) are redirected directly
+/// 
+/// # Example
+/// 
+/// ```
+/// use axum_responses::extra::external_redirect_via;
+/// 
+/// let redirect = external_redirect_via("/leaving", "https://other.com/?a=1", &["app.example.com"]);
+/// // Location: /leaving?target=https%3A%2F%2Fother.com%2F%3Fa%3D1
+/// ```
+pub fn external_redirect_via(interstitial: &str, target: &str, internal_hosts: &[&str]) -> Redirect {
+
+    if is_safe_location(target, internal_hosts) {
+        return Redirect::to(target)
+    }
+
+    let separator = match interstitial.contains('?') {
+        true => '&',
+        false => '?'
+    };
+
+    Redirect::to(&format!("{interstitial}{separator}target={}", percent_encode(target)))
+}
+
+/// Build a 303 See Other redirect that carries over the query string
+/// of the original request URI, merged after the query of the location
+/// 
+/// When a key is present in both, the location's values win and the original
+/// ones are dropped. Empty pairs (`a=1&&b=2`) are skipped and a fragment in 
+/// the location is kept at the end
+/// 
+/// # Example
+/// 
+/// ```
+/// use axum::http::Uri;
+/// use axum_responses::extra::redirect_with_query;
+/// 
+/// let original = Uri::from_static("/old?a=1&b=2");
+/// let redirect = redirect_with_query("/new?c=3", &original);
+/// // Location: /new?c=3&a=1&b=2
+/// ```
+pub fn redirect_with_query(location: &str, original: &Uri) -> Redirect {
+
+    let (location, fragment) = match location.split_once('#') {
+        Some((location, fragment)) => (location, Some(fragment)),
+        None => (location, None)
+    };
+
+    let (path, query) = location.split_once('?').unwrap_or((location, ""));
+
+    let location_pairs = query.split('&')
+        .filter(|pair| !pair.is_empty())
+        .collect::<Vec<&str>>()
+    ;
+
+    let original_pairs = original.query().unwrap_or_default().split('&')
+        .filter(|pair| !pair.is_empty())
+        .filter(|pair| !location_pairs.iter().any(|own| query_key(own) == query_key(pair)))
+    ;
+
+    let pairs = location_pairs.iter().copied()
+        .chain(original_pairs)
+        .collect::<Vec<&str>>()
+    ;
+
+    let mut merged = path.to_string();
+
+    if !pairs.is_empty() {
+        merged.push('?');
+        merged.push_str(&pairs.join("&"));
+    }
+
+    if let Some(fragment) = fragment {
+        merged.push('#');
+        merged.push_str(fragment);
+    }
+
+    Redirect::to(&merged)
+}
+
+fn query_key(pair: &str) -> &str {
+    pair.split('=').next().unwrap_or_default()
+}
+
+/// Build a 303 See Other redirect with an absolute location, resolving 
+/// a relative location against the scheme and authority of `base` 
+/// (usually the request URI), for clients that require absolute URLs
+/// 
+/// `.` and `..` path segments are normalized, locations that already have
+/// a scheme are used unchanged and protocol-relative locations (`//host/a`) 
+/// take the scheme of `base`. If `base` has no scheme or authority 
+/// the location is used as-is
+/// 
+/// # Example
+/// 
+/// ```
+/// use axum::http::Uri;
+/// use axum_responses::extra::absolute_redirect;
+/// 
+/// let base = Uri::from_static("https://example.com/docs/guide/intro");
+/// let redirect = absolute_redirect("../api", &base);
+/// // Location: https://example.com/docs/api
+/// ```
+pub fn absolute_redirect(location: &str, base: &Uri) -> Redirect {
+
+    let (Some(scheme), Some(authority)) = (base.scheme_str(), base.authority()) else {
+        return Redirect::to(location)
+    };
+
+    if !is_relative_reference(location) {
+        return Redirect::to(location)
+    }
+
+    if location.starts_with("//") {
+        return Redirect::to(&format!("{scheme}:{location}"))
+    }
+
+    let (path, suffix) = location.split_at(location.find(['?', '#']).unwrap_or(location.len()));
+
+    let path = match path {
+        "" => base.path().to_string(),
+        path if path.starts_with('/') => path.to_string(),
+        path => {
+            let directory = &base.path()[..=base.path().rfind('/').unwrap_or_default()];
+            format!("{directory}{path}")
+        }
+    };
+
+    Redirect::to(&format!("{scheme}://{authority}{}{suffix}", remove_dot_segments(&path)))
+}
+
+/// Remove `.` and `..` segments from an absolute path (RFC 3986, section 5.2.4)
+fn remove_dot_segments(path: &str) -> String {
+
+    let segments = path.split('/').skip(1).collect::<Vec<&str>>();
+    let mut output = Vec::new();
+
+    for segment in &segments {
+        match *segment {
+            "." => {},
+            ".." => { output.pop(); },
+            segment => output.push(segment)
+        }
+    }
+
+    if matches!(segments.last(), Some(&".") | Some(&"..")) {
+        output.push("");
+    }
+
+    format!("/{}", output.join("/"))
+}
+
+/// A location is safe when it is a relative reference, or an absolute 
+/// `http`/`https` location whose host is allowed and has no userinfo
+fn is_safe_location(location: &str, allowed_hosts: &[&str]) -> bool {
+
+    let is_unsafe = location.is_empty()
+        || location.starts_with("//")
+        || location.chars().any(|c| c == '\\' || c.is_whitespace() || c.is_control())
+    ;
+
+    if is_unsafe {
+        return false
+    }
+
+    if is_relative_reference(location) {
+        return true
+    }
+
+    let Ok(uri) = location.parse::<Uri>() else {
+        return false
+    };
+
+    let is_http = uri.scheme_str()
+        .is_some_and(|scheme| scheme.eq_ignore_ascii_case("http") || scheme.eq_ignore_ascii_case("https"))
+    ;
+
+    let is_allowed = uri.authority()
+        .filter(|authority| !authority.as_str().contains('@'))
+        .is_some_and(|authority| allowed_hosts.iter().any(|host| host.eq_ignore_ascii_case(authority.host())))
+    ;
+
+    is_http && is_allowed
+}
+
+/// A relative reference can't have a colon before the first `/`, `?` or `#`
+fn is_relative_reference(location: &str) -> bool {
+    !location.split(['/', '?', '#']).next().unwrap_or_default().contains(':')
+}
+
+/// Percent-encode everything but the RFC 3986 unreserved characters
+fn percent_encode(value: &str) -> String {
+
+    value.bytes().map(|byte| match byte {
+        b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'.' | b'_' | b'~' => (byte as char).to_string(),
+        _ => format!("%{byte:02X}")
+    }).collect()
+}
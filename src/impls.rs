@@ -1,8 +1,28 @@
 
-use serde_json::json;
+use std::env::VarError;
+
+use serde::Serialize;
+use serde_json::{json, to_value, Value};
+use serde_json::error::Category;
+
+#[cfg(feature = "validator")]
+use serde_json::Map;
+
+#[cfg(feature = "validator")]
+use validator::{ValidationErrors, ValidationErrorsKind};
+
+#[cfg(feature = "tower")]
+use axum::BoxError;
+
+#[cfg(feature = "tower")]
+use tower::timeout::error::Elapsed;
+
+#[cfg(feature = "csv")]
+use crate::Csv;
 
 use crate::{
     res_type, 
+    Html,
     Response, 
     HttpResponse, 
     to_http_status,
@@ -10,12 +30,19 @@ use crate::{
 
 use axum::{
     
-    Json, response::{
+    Json, http::{header, StatusCode}, response::{
         IntoResponse, 
         Response as AxumResponse,
     }, 
 };
 
+/// 204 No Content and 304 Not Modified responses must not have a body,
+/// so these are sent without the JSON envelope and Content-Type,
+/// any `JsonData` value is dropped
+fn is_bodyless(code: &StatusCode) -> bool {
+    matches!(*code, StatusCode::NO_CONTENT | StatusCode::NOT_MODIFIED)
+}
+
 impl IntoResponse for Response {
 
     fn into_response(self) -> AxumResponse {
@@ -26,19 +53,27 @@ impl IntoResponse for Response {
 
                 let code = to_http_status(status);
 
+                if is_bodyless(&code) {
+                    return code.into_response()
+                }
+
                 let data = json!({ 
                     "status_code": code.as_u16(), 
                     "message": message, 
                     "type": res_type(&code)
                 });
 
-                return (code, Json(data)).into_response()
+                (code, Json(data)).into_response()
             },
 
             Response::JsonData(status, message, data_name, data) => {
 
                 let code = to_http_status(status);
 
+                if is_bodyless(&code) {
+                    return code.into_response()
+                }
+
                 let data = json!({
                     "status_code": code.as_u16(), 
                     "message": message, 
@@ -46,7 +81,7 @@ impl IntoResponse for Response {
                     "type": res_type(&code)
                 });
 
-                return (code, Json(data)).into_response()
+                (code, Json(data)).into_response()
             }
         }
     }
@@ -55,48 +90,285 @@ impl IntoResponse for Response {
 impl IntoResponse for HttpResponse {
     fn into_response(self) -> AxumResponse {
         match self {
-            HttpResponse::CONTINUE => Response::Standard(100, "Continue").into_response(),
-            HttpResponse::SWITCHING_PROTOCOLS => Response::Standard(101, "Switching Protocols").into_response(),
-            HttpResponse::OK => Response::Standard(200, "OK").into_response(),
-            HttpResponse::CREATED => Response::Standard(201, "Created").into_response(),
-            HttpResponse::ACCEPTED => Response::Standard(202, "Accepted").into_response(),
-            HttpResponse::NON_AUTHORITATIVE_INFORMATION => Response::Standard(203, "Non-Authoritative Information").into_response(),
-            HttpResponse::NO_CONTENT => Response::Standard(204, "No Content").into_response(),
-            HttpResponse::RESET_CONTENT => Response::Standard(205, "Reset Content").into_response(),
-            HttpResponse::PARTIAL_CONTENT => Response::Standard(206, "Partial Content").into_response(),
-            HttpResponse::MULTIPLE_CHOICES => Response::Standard(300, "Multiple Choices").into_response(),
-            HttpResponse::MOVED_PERMANENTLY => Response::Standard(301, "Moved Permanently").into_response(),
-            HttpResponse::FOUND => Response::Standard(302, "Found").into_response(),
-            HttpResponse::SEE_OTHER => Response::Standard(303, "See Other").into_response(),
-            HttpResponse::NOT_MODIFIED => Response::Standard(304, "Not Modified").into_response(),
-            HttpResponse::USE_PROXY => Response::Standard(305, "Use Proxy").into_response(),
-            HttpResponse::TEMPORARY_REDIRECT => Response::Standard(307, "Temporary Redirect").into_response(),
-            HttpResponse::BAD_REQUEST => Response::Standard(400, "Bad Request").into_response(),
-            HttpResponse::UNAUTHORIZED => Response::Standard(401, "Unauthorized").into_response(),
-            HttpResponse::PAYMENT_REQUIRED => Response::Standard(402, "Payment Required").into_response(),
-            HttpResponse::FORBIDDEN => Response::Standard(403, "Forbidden").into_response(),
-            HttpResponse::NOT_FOUND => Response::Standard(404, "Not Found").into_response(),
-            HttpResponse::METHOD_NOT_ALLOWED => Response::Standard(405, "Method Not Allowed").into_response(),
-            HttpResponse::NOT_ACCEPTABLE => Response::Standard(406, "Not Acceptable").into_response(),
-            HttpResponse::PROXY_AUTHENTICATION_REQUIRED => Response::Standard(407, "Proxy Authentication Required").into_response(),
-            HttpResponse::REQUEST_TIMEOUT => Response::Standard(408, "Request Timeout").into_response(),
-            HttpResponse::CONFLICT => Response::Standard(409, "Conflict").into_response(),
-            HttpResponse::GONE => Response::Standard(410, "Gone").into_response(),
-            HttpResponse::LENGTH_REQUIRED => Response::Standard(411, "Length Required").into_response(),
-            HttpResponse::PRECONDITION_FAILED => Response::Standard(412, "Precondition Failed").into_response(),
-            HttpResponse::REQUEST_ENTITY_TOO_LARGE => Response::Standard(413, "Request Entity Too Large").into_response(),
-            HttpResponse::REQUEST_URI_TOO_LONG => Response::Standard(414, "Request-URI Too Long").into_response(),
-            HttpResponse::UNSUPPORTED_MEDIA_TYPE => Response::Standard(415, "Unsupported Media Type").into_response(),
-            HttpResponse::REQUESTED_RANGE_NOT_SATISFIABLE => Response::Standard(416, "Requested Range Not Satisfiable").into_response(),
-            HttpResponse::EXPECTATION_FAILED => Response::Standard(417, "Expectation Failed").into_response(),
-            HttpResponse::INTERNAL_SERVER_ERROR => Response::Standard(500, "Internal Server Error").into_response(),
-            HttpResponse::NOT_IMPLEMENTED => Response::Standard(501, "Not Implemented").into_response(),
-            HttpResponse::BAD_GATEWAY => Response::Standard(502, "Bad Gateway").into_response(),
-            HttpResponse::SERVICE_UNAVAILABLE => Response::Standard(503, "Service Unavailable").into_response(),
-            HttpResponse::GATEWAY_TIMEOUT => Response::Standard(504, "Gateway Timeout").into_response(),
-            HttpResponse::HTTP_VERSION_NOT_SUPPORTED => Response::Standard(505, "HTTP Version Not Supported").into_response(),
-            HttpResponse::CUSTOM(status, message) => Response::Standard(status, message).into_response(),
             HttpResponse::JSON(status, message, key, value) => Response::JsonData(status, message, key, value).into_response(),
+            response => {
+                let (status, message) = response.parts();
+                Response::Standard(status, message).into_response()
+            }
+        }
+    }
+}
+
+impl IntoResponse for Html {
+
+    fn into_response(self) -> AxumResponse {
+
+        let headers = [(header::CONTENT_TYPE, "text/html; charset=utf-8")];
+
+        (to_http_status(self.status), headers, self.content).into_response()
+    }
+}
+
+#[cfg(feature = "csv")]
+impl IntoResponse for Csv {
+
+    fn into_response(self) -> AxumResponse {
+
+        let Some(content) = self.content else {
+            return HttpResponse::INTERNAL_SERVER_ERROR.into_response()
+        };
+
+        let filename = self.filename.chars()
+            .map(|c| if c.is_control() { '_' } else { c })
+            .collect::<String>()
+            .replace('\\', "\\\\")
+            .replace('"', "\\\"")
+        ;
+
+        let disposition = format!("attachment; filename=\"{filename}\"");
+
+        let headers = [
+            (header::CONTENT_TYPE, "text/csv; charset=utf-8".to_string()),
+            (header::CONTENT_DISPOSITION, disposition),
+        ];
+
+        (headers, content).into_response()
+    }
+}
+
+impl HttpResponse {
+
+    /// Shorthand for a 200 OK response with the provided data under the `data` key
+    pub fn ok_with<T: Serialize>(data: T) -> Self {
+        HttpResponse::with_key(200, "data", data)
+    }
+
+    /// Shorthand for a 201 Created response with the provided data under the `data` key
+    pub fn created_with<T: Serialize>(data: T) -> Self {
+        HttpResponse::with_key(201, "data", data)
+    }
+
+    /// Shorthand for a response with the provided status code 
+    /// and the error under the `error` key
+    pub fn error_with<T: Serialize>(status: u16, error: T) -> Self {
+        HttpResponse::with_key(status, "error", error)
+    }
+
+    /// Re-wrap a downstream status and JSON body into the standard response.
+    /// Object bodies go under the `error` key when the status is not successful,
+    /// anything else goes under the `data` key.
+    /// 
+    /// The message is the standard reason phrase of the status code,
+    /// a `message` field of the downstream body is kept inside the body 
+    /// but not used as the response message.
+    pub fn from_downstream(status: u16, body: Value) -> Self {
+
+        match body.is_object() && !to_http_status(status).is_success() {
+            true => HttpResponse::with_key(status, "error", body),
+            false => HttpResponse::with_key(status, "data", body)
+        }
+    }
+
+    /// The HTTP status code of the response.
+    /// Invalid custom codes are reported as a 500 Internal Server Error,
+    /// as that is the status the response will be sent with
+    pub fn status_code(&self) -> StatusCode {
+        to_http_status(self.parts().0)
+    }
+
+    /// The message that will be sent in the response body
+    pub fn message(&self) -> &'static str {
+        self.parts().1
+    }
+
+    /// Whether the response carries a JSON value under the `data` key
+    pub fn has_data(&self) -> bool {
+        matches!(self, HttpResponse::JSON(_, _, "data", _))
+    }
+
+    /// Whether the response carries a JSON value under the `error` or `errors` key
+    pub fn has_error(&self) -> bool {
+        matches!(self, HttpResponse::JSON(_, _, "error" | "errors", _))
+    }
+
+    fn parts(&self) -> (u16, &'static str) {
+        match self {
+            HttpResponse::CONTINUE => (100, "Continue"),
+            HttpResponse::SWITCHING_PROTOCOLS => (101, "Switching Protocols"),
+            HttpResponse::OK => (200, "OK"),
+            HttpResponse::CREATED => (201, "Created"),
+            HttpResponse::ACCEPTED => (202, "Accepted"),
+            HttpResponse::NON_AUTHORITATIVE_INFORMATION => (203, "Non-Authoritative Information"),
+            HttpResponse::NO_CONTENT => (204, "No Content"),
+            HttpResponse::RESET_CONTENT => (205, "Reset Content"),
+            HttpResponse::PARTIAL_CONTENT => (206, "Partial Content"),
+            HttpResponse::MULTIPLE_CHOICES => (300, "Multiple Choices"),
+            HttpResponse::MOVED_PERMANENTLY => (301, "Moved Permanently"),
+            HttpResponse::FOUND => (302, "Found"),
+            HttpResponse::SEE_OTHER => (303, "See Other"),
+            HttpResponse::NOT_MODIFIED => (304, "Not Modified"),
+            HttpResponse::USE_PROXY => (305, "Use Proxy"),
+            HttpResponse::TEMPORARY_REDIRECT => (307, "Temporary Redirect"),
+            HttpResponse::BAD_REQUEST => (400, "Bad Request"),
+            HttpResponse::UNAUTHORIZED => (401, "Unauthorized"),
+            HttpResponse::PAYMENT_REQUIRED => (402, "Payment Required"),
+            HttpResponse::FORBIDDEN => (403, "Forbidden"),
+            HttpResponse::NOT_FOUND => (404, "Not Found"),
+            HttpResponse::METHOD_NOT_ALLOWED => (405, "Method Not Allowed"),
+            HttpResponse::NOT_ACCEPTABLE => (406, "Not Acceptable"),
+            HttpResponse::PROXY_AUTHENTICATION_REQUIRED => (407, "Proxy Authentication Required"),
+            HttpResponse::REQUEST_TIMEOUT => (408, "Request Timeout"),
+            HttpResponse::CONFLICT => (409, "Conflict"),
+            HttpResponse::GONE => (410, "Gone"),
+            HttpResponse::LENGTH_REQUIRED => (411, "Length Required"),
+            HttpResponse::PRECONDITION_FAILED => (412, "Precondition Failed"),
+            HttpResponse::REQUEST_ENTITY_TOO_LARGE => (413, "Request Entity Too Large"),
+            HttpResponse::REQUEST_URI_TOO_LONG => (414, "Request-URI Too Long"),
+            HttpResponse::UNSUPPORTED_MEDIA_TYPE => (415, "Unsupported Media Type"),
+            HttpResponse::REQUESTED_RANGE_NOT_SATISFIABLE => (416, "Requested Range Not Satisfiable"),
+            HttpResponse::EXPECTATION_FAILED => (417, "Expectation Failed"),
+            HttpResponse::INTERNAL_SERVER_ERROR => (500, "Internal Server Error"),
+            HttpResponse::NOT_IMPLEMENTED => (501, "Not Implemented"),
+            HttpResponse::BAD_GATEWAY => (502, "Bad Gateway"),
+            HttpResponse::SERVICE_UNAVAILABLE => (503, "Service Unavailable"),
+            HttpResponse::GATEWAY_TIMEOUT => (504, "Gateway Timeout"),
+            HttpResponse::HTTP_VERSION_NOT_SUPPORTED => (505, "HTTP Version Not Supported"),
+            HttpResponse::CUSTOM(status, message) => (*status, message),
+            HttpResponse::JSON(status, message, _, _) => (*status, message),
+        }
+    }
+
+    fn with_key<T: Serialize>(status: u16, key: &'static str, value: T) -> Self {
+
+        let message = to_http_status(status).canonical_reason().unwrap_or("Unknown");
+        let value = to_value(value).unwrap_or(Value::Null);
+
+        HttpResponse::JSON(status, message, key, value)
+    }
+}
+
+/// Invalid input (malformed JSON, unexpected end of input, missing fields 
+/// or mismatched types) is treated as a client error and becomes a 
+/// 400 Bad Request, IO errors become a 500 Internal Server Error.
+/// The error details are not exposed
+/// 
+/// `serde_json::Error` doesn't tell parsing and serialization apart, so a 
+/// failed serialization (e.g. a map with non-string keys or a custom 
+/// `Serialize` error) also becomes a 400 Bad Request. When serializing 
+/// server values, map the error explicitly instead of using `?`:
+/// 
+/// ```rust
+/// use axum_responses::{AxumResult, HttpResponse};
+/// 
+/// fn render(value: &serde_json::Value) -> AxumResult<String> {
+///     serde_json::to_string(value).map_err(|_| HttpResponse::INTERNAL_SERVER_ERROR)
+/// }
+/// ```
+impl From<serde_json::Error> for HttpResponse {
+
+    fn from(error: serde_json::Error) -> Self {
+
+        match error.classify() {
+            Category::Syntax | Category::Eof | Category::Data => HttpResponse::BAD_REQUEST,
+            Category::Io => HttpResponse::INTERNAL_SERVER_ERROR
+        }
+    }
+}
+
+/// Any IO error becomes a 500 Internal Server Error.
+/// The error details are not exposed
+impl From<std::io::Error> for HttpResponse {
+
+    fn from(_: std::io::Error) -> Self {
+        HttpResponse::INTERNAL_SERVER_ERROR
+    }
+}
+
+/// A missing or non-unicode environment variable becomes a 500 response
+/// reporting a configuration problem, the variable value is not exposed
+impl From<VarError> for HttpResponse {
+
+    fn from(error: VarError) -> Self {
+
+        match error {
+            VarError::NotPresent => HttpResponse::CUSTOM(500, "Missing configuration"),
+            VarError::NotUnicode(_) => HttpResponse::CUSTOM(500, "Invalid configuration")
+        }
+    }
+}
+
+/// Build a 422 Unprocessable Entity response from the `validator` crate errors.
+/// Each field is mapped to the list of its messages (or error codes when 
+/// no message was provided) under the `errors` key. Nested structs and 
+/// lists are flattened into dotted paths like `address.city` or `items[0].name`
+#[cfg(feature = "validator")]
+impl From<ValidationErrors> for HttpResponse {
+
+    fn from(errors: ValidationErrors) -> Self {
+
+        let mut fields = Map::new();
+        collect_validation_errors(&errors, None, &mut fields);
+
+        HttpResponse::JSON(422, "Unprocessable Entity", "errors", Value::Object(fields))
+    }
+}
+
+#[cfg(feature = "validator")]
+fn collect_validation_errors(errors: &ValidationErrors, prefix: Option<&str>, fields: &mut Map<String, Value>) {
+
+    for (field, kind) in errors.errors() {
+
+        let path = match prefix {
+            Some(prefix) => format!("{prefix}.{field}"),
+            None => field.to_string()
+        };
+
+        match kind {
+
+            ValidationErrorsKind::Field(errors) => {
+
+                let messages = errors.iter()
+                    .map(|error| Value::String(error.message.as_ref().unwrap_or(&error.code).to_string()))
+                    .collect()
+                ;
+
+                fields.insert(path, Value::Array(messages));
+            },
+
+            ValidationErrorsKind::Struct(errors) => {
+                collect_validation_errors(errors, Some(&path), fields)
+            },
+
+            ValidationErrorsKind::List(items) => {
+                for (index, errors) in items {
+                    collect_validation_errors(errors, Some(&format!("{path}[{index}]")), fields)
+                }
+            }
+        }
+    }
+}
+
+/// Map a `tower` timeout into a 408 Request Timeout response.
+/// Useful inside an axum `HandleErrorLayer` wrapping a `TimeoutLayer`
+#[cfg(feature = "tower")]
+impl From<Elapsed> for HttpResponse {
+
+    fn from(_: Elapsed) -> Self {
+        HttpResponse::REQUEST_TIMEOUT
+    }
+}
+
+/// Map an error produced by a `tower` middleware into a response.
+/// Timeouts become a 408 Request Timeout and any other error becomes a 
+/// 500 Internal Server Error, the inner error is not exposed to the client
+#[cfg(feature = "tower")]
+impl From<BoxError> for HttpResponse {
+
+    fn from(error: BoxError) -> Self {
+
+        match error.downcast::<Elapsed>() {
+            Ok(elapsed) => HttpResponse::from(*elapsed),
+            Err(_) => HttpResponse::INTERNAL_SERVER_ERROR
         }
     }
 }
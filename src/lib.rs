@@ -1,14 +1,15 @@
 
+#[cfg(test)]
 mod tests;
 pub mod extra;
 pub mod impls;
 
 use extra::*;
 use serde_json::Value;
+use std::borrow::Cow;
 
 /// `AxumResponse` data type that represents an HTTP response. 
 /// Can be used as a return type of a controller.
- 
 pub type AxumResponse = Result<HttpResponse, HttpResponse>;
 
 /// `AxumResult` data type that represents a response 
@@ -22,9 +23,67 @@ pub type AxumResponse = Result<HttpResponse, HttpResponse>;
 /// Returns a type T if the response is successful, 
 /// otherwise it returns a negative `ApiResponse`, 
 /// that is, an error HttpResponse.
- 
+/// 
+/// When `T` implements `IntoResponse` (e.g. `axum::response::Redirect`,
+/// or this crate's `Html` and `Csv`), `AxumResult<T>` can also be returned 
+/// from a controller, errors are rendered as the JSON HttpResponse.
 pub type AxumResult<T> = Result<T, HttpResponse>;
 
+/// `http_bail` returns early from the current function with an error response.
+/// 
+/// ### Parameters
+/// 
+/// * `u16`, `&'static str`: HTTP status code and message of a custom response.
+/// * or a single `HttpResponse`.
+/// 
+/// ### Example
+/// 
+/// ```rust
+/// use axum_responses::{http_bail, AxumResponse, HttpResponse};
+/// 
+/// async fn find_user(id: u32) -> AxumResponse {
+/// 
+///     if id == 0 {
+///         http_bail!(HttpResponse::BAD_REQUEST);
+///     }
+/// 
+///     http_bail!(404, "User not found")
+/// }
+/// ```
+#[macro_export]
+macro_rules! http_bail {
+    ($status:expr, $message:expr) => {
+        return Err($crate::HttpResponse::CUSTOM($status, $message).into())
+    };
+    ($response:expr) => {
+        return Err($response.into())
+    };
+}
+
+/// `http_ensure` returns early with an error response if the condition is false,
+/// taking the same response arguments as `http_bail`.
+/// 
+/// ### Example
+/// 
+/// ```rust
+/// use axum_responses::{http_ensure, AxumResponse, HttpResponse};
+/// 
+/// async fn update_user(is_owner: bool) -> AxumResponse {
+/// 
+///     http_ensure!(is_owner, 403, "You can't update this user");
+/// 
+///     Ok(HttpResponse::OK)
+/// }
+/// ```
+#[macro_export]
+macro_rules! http_ensure {
+    ($condition:expr, $($response:tt)+) => {
+        if !$condition {
+            $crate::http_bail!($($response)+);
+        }
+    };
+}
+
 pub enum Response {
     
     /// `Standard` is a standard response.
@@ -118,3 +177,109 @@ pub enum HttpResponse {
     CUSTOM(u16, &'static str),
     JSON(u16, &'static str, &'static str, Value),
 }
+
+/// `Html` is a response for simple server-rendered pages,
+/// sent with a `text/html; charset=utf-8` content type and a 200 OK status 
+/// unless another one is provided.
+/// 
+/// Unlike `axum::response::Html`, which has to be wrapped in a 
+/// `(StatusCode, Html(..))` tuple to change its status, the status code 
+/// can be set directly with the `status` builder method.
+/// 
+/// ### Example
+/// 
+/// ```rust
+/// use axum_responses::{AxumResult, Html};
+/// 
+/// async fn dashboard() -> AxumResult<Html> {
+///     Ok(Html::new("<h1>Everything is fine</h1>"))
+/// }
+/// 
+/// async fn maintenance() -> AxumResult<Html> {
+///     Ok(Html::new("<h1>Come back later</h1>").status(503))
+/// }
+/// ```
+pub struct Html {
+    content: Cow<'static, str>,
+    status: u16,
+}
+
+impl Html {
+
+    /// Create a page from a static or owned string
+    pub fn new<T: Into<Cow<'static, str>>>(content: T) -> Self {
+        Html { content: content.into(), status: 200 }
+    }
+
+    /// Set the HTTP status code of the response,
+    /// invalid codes are sent as a 500 Internal Server Error
+    pub fn status(mut self, status: u16) -> Self {
+        self.status = status;
+        self
+    }
+}
+
+/// `Csv` is a response that serializes a list of records as a CSV file download.
+/// The header row is derived from the record field names.
+/// 
+/// If a record can't be serialized, a 500 Internal Server Error 
+/// HttpResponse is returned instead. When there are no records 
+/// the body is empty, as the header row is derived from the first one.
+/// 
+/// ### Example
+/// 
+/// ```rust
+/// use axum_responses::{AxumResult, Csv};
+/// use serde::Serialize;
+/// 
+/// #[derive(Serialize)]
+/// struct User {
+///     name: String,
+///     age: u8
+/// }
+/// 
+/// async fn export_users() -> AxumResult<Csv> {
+/// 
+///     let users = vec![
+///         User { name: "John".to_string(), age: 25 },
+///         User { name: "Jane".to_string(), age: 30 },
+///     ];
+/// 
+///     Ok(Csv::new(users).filename("users.csv"))
+/// }
+/// ```
+#[cfg(feature = "csv")]
+pub struct Csv {
+    content: Option<Vec<u8>>,
+    filename: Cow<'static, str>,
+}
+
+#[cfg(feature = "csv")]
+impl Csv {
+
+    /// Serialize the records into an in-memory CSV buffer,
+    /// the default download filename is `export.csv`
+    pub fn new<I, T>(records: I) -> Self where I: IntoIterator<Item = T>, T: serde::Serialize {
+
+        let mut writer = csv::Writer::from_writer(Vec::new());
+
+        let written = records.into_iter()
+            .try_for_each(|record| writer.serialize(record))
+        ;
+
+        let content = match written {
+            Ok(_) => writer.into_inner().ok(),
+            Err(_) => None
+        };
+
+        Csv { content, filename: Cow::Borrowed("export.csv") }
+    }
+
+    /// Set the filename of the `Content-Disposition` header,
+    /// quotes and backslashes are escaped and control characters 
+    /// (e.g. line breaks) are replaced with `_` when the header is built
+    pub fn filename<T: Into<Cow<'static, str>>>(mut self, filename: T) -> Self {
+        self.filename = filename.into();
+        self
+    }
+}